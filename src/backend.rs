@@ -0,0 +1,112 @@
+//! A thin HTTP client for the Plex server itself, used by slash commands
+//! that need live library data rather than just parsing strings.
+//!
+//! Slash command handlers don't receive the `Project` that
+//! `context_server_command` gets, so they can't reach
+//! `ContextServerSettings` directly. `PlexMediaServerExtension` captures
+//! `PLEX_URL`/`PLEX_TOKEN` the one time it does have project access and
+//! hands them to these functions instead.
+
+use zed_extension_api::{self as zed, http_client, serde_json};
+
+/// Credentials needed to call a Plex server's HTTP API.
+#[derive(Debug, Clone)]
+pub struct PlexClient {
+    pub base_url: String,
+    pub token: String,
+}
+
+impl PlexClient {
+    pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            token: token.into(),
+        }
+    }
+
+    /// GET `path` against the configured server and parse the response body
+    /// as JSON (Plex's API returns JSON when asked for it via `Accept`).
+    pub fn get_json(&self, path: &str) -> zed::Result<serde_json::Value, String> {
+        let url = format!("{}{path}", self.base_url.trim_end_matches('/'));
+        let request = http_client::HttpRequest {
+            method: http_client::HttpMethod::Get,
+            url,
+            headers: vec![
+                ("X-Plex-Token".to_string(), self.token.clone()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ],
+            body: None,
+            redirect_policy: http_client::RedirectPolicy::FollowAll,
+        };
+
+        let response = http_client::fetch(&request)?;
+        serde_json::from_slice(&response.body)
+            .map_err(|err| format!("failed to parse Plex response from {path}: {err}"))
+    }
+
+    /// The `Metadata` array Plex nests most list/search responses under.
+    pub fn metadata_items(&self, path: &str) -> zed::Result<Vec<serde_json::Value>, String> {
+        self.container_items(path, "Metadata")
+    }
+
+    /// Like `metadata_items`, but for the endpoints (e.g. `/library/sections`)
+    /// that nest their array under `Directory` instead.
+    pub fn container_items(&self, path: &str, key: &str) -> zed::Result<Vec<serde_json::Value>, String> {
+        let body = self.get_json(path)?;
+        Ok(body
+            .get("MediaContainer")
+            .and_then(|container| container.get(key))
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// The configured server's own `machineIdentifier`, used to detect a
+    /// deep link that points at a different Plex server than this one.
+    pub fn machine_identifier(&self) -> zed::Result<String, String> {
+        let body = self.get_json("/identity")?;
+        body.get("MediaContainer")
+            .and_then(|container| container.get("machineIdentifier"))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| "Plex /identity response had no machineIdentifier".to_string())
+    }
+}
+
+/// Percent-encode a string for use inside a URL query component.
+///
+/// Plex's HTTP API is otherwise plain, so this only needs to cover the
+/// characters a search query or title realistically contains.
+pub fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_unreserved_characters_untouched() {
+        assert_eq!(percent_encode("abcXYZ019-_.~"), "abcXYZ019-_.~");
+    }
+
+    #[test]
+    fn encodes_spaces_and_reserved_characters() {
+        assert_eq!(percent_encode("slow burn"), "slow%20burn");
+        assert_eq!(percent_encode("a&b=c?d"), "a%26b%3Dc%3Fd");
+    }
+
+    #[test]
+    fn encodes_multi_byte_unicode_as_individual_utf8_bytes() {
+        assert_eq!(percent_encode("café"), "caf%C3%A9");
+    }
+}
@@ -0,0 +1,123 @@
+//! Resolving links a user pastes in from the Plex apps.
+//!
+//! Plex hands out two different link shapes for the same underlying pair
+//! of facts (which server, which item): its own
+//! `plex://server/{machineIdentifier}/{key}` scheme, and the web app's
+//! `https://app.plex.tv/desktop/#!/...&key=...&metadataKey=...` URLs, whose
+//! routing state lives in `&`-joined fragment parameters rather than a real
+//! query string. `DeepLink::parse` normalizes both into the server +
+//! metadata key that `/plex-link` needs to fetch the item.
+
+/// A resolved Plex deep link: which server it points at, and which item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLink {
+    pub machine_identifier: Option<String>,
+    pub metadata_key: String,
+}
+
+impl DeepLink {
+    /// Parse a `plex://` URI or an `app.plex.tv` web link.
+    ///
+    /// Returns `None` if `raw` is neither shape, or is missing the metadata
+    /// key needed to resolve an item.
+    pub fn parse(raw: &str) -> Option<DeepLink> {
+        if let Some(rest) = raw.strip_prefix("plex://server/") {
+            let mut parts = rest.splitn(2, '/');
+            let machine_identifier = parts.next().filter(|s| !s.is_empty());
+            let metadata_key = parts.next()?;
+            return Some(DeepLink {
+                machine_identifier: machine_identifier.map(str::to_string),
+                metadata_key: format!("/{metadata_key}"),
+            });
+        }
+
+        let fragment = raw.split_once('#').map(|(_, fragment)| fragment)?;
+        let query = fragment.split_once('?').map(|(_, query)| query).unwrap_or(fragment);
+
+        let mut machine_identifier = None;
+        let mut metadata_key = None;
+        for pair in query.split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let value = percent_decode(value);
+            match key {
+                "server" | "machineIdentifier" => machine_identifier = Some(value),
+                "metadataKey" | "key" => metadata_key = Some(value),
+                _ => {}
+            }
+        }
+
+        Some(DeepLink {
+            machine_identifier,
+            metadata_key: metadata_key?,
+        })
+    }
+}
+
+/// Decode a percent-encoded URL component, e.g. `%2Flibrary%2Fmetadata%2F1`
+/// -> `/library/metadata/1`. Invalid escapes are passed through unchanged
+/// rather than rejected, since a malformed link is still worth a best
+/// effort at resolving.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(hex) = input.get(i + 1..i + 3) {
+                if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plex_scheme_uri() {
+        assert_eq!(
+            DeepLink::parse("plex://server/abcd1234/library/metadata/12345"),
+            Some(DeepLink {
+                machine_identifier: Some("abcd1234".to_string()),
+                metadata_key: "/library/metadata/12345".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_web_link_and_percent_decodes_the_metadata_key() {
+        let link = "https://app.plex.tv/desktop/#!/details?server=abcd1234&key=%2Flibrary%2Fmetadata%2F12345&metadataKey=%2Flibrary%2Fmetadata%2F12345";
+        assert_eq!(
+            DeepLink::parse(link),
+            Some(DeepLink {
+                machine_identifier: Some("abcd1234".to_string()),
+                metadata_key: "/library/metadata/12345".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn returns_none_when_metadata_key_is_missing() {
+        assert_eq!(
+            DeepLink::parse("https://app.plex.tv/desktop/#!/details?server=abcd1234"),
+            None
+        );
+        assert_eq!(DeepLink::parse("not a plex link"), None);
+    }
+
+    #[test]
+    fn percent_decode_passes_through_invalid_escapes() {
+        assert_eq!(percent_decode("abc%2"), "abc%2");
+        assert_eq!(percent_decode("abc%zz"), "abc%zz");
+    }
+}
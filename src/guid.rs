@@ -0,0 +1,169 @@
+//! Cross-referencing Plex library items against external databases.
+//!
+//! Plex metadata agents have changed shape several times over the years,
+//! and the `guid` field carries the scars: old agents wrote
+//! `com.plexapp.agents.imdb://tt0111161?lang=en`, current ones write plain
+//! `imdb://tt0111161` / `tmdb://278` / `tvdb://81189`, and the newest
+//! ones attach a `Guid: [{ "id": "imdb://tt0111161" }, ...]` array instead
+//! of (or alongside) the top-level string. `Guid::parse_all` normalizes
+//! all of that into one list so the `/plex-guids` command can just print
+//! whatever it finds.
+
+use zed_extension_api::serde_json;
+
+const LEGACY_AGENT_PREFIX: &str = "com.plexapp.agents.";
+
+/// A Plex `guid`, resolved to the external database it references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Guid {
+    Imdb(String),
+    Tmdb(String),
+    Tvdb(String),
+    Mbid(String),
+    Plex(String),
+    Local(String),
+    Collection(String),
+    /// Any other `guid`: a `scheme://id` pair whose scheme isn't one of the
+    /// above, or a string with no `scheme://id` shape at all. Holds the raw
+    /// input unchanged.
+    Unknown(String),
+}
+
+impl Guid {
+    /// Parse a raw Plex `guid` string.
+    ///
+    /// Strips any `com.plexapp.agents.` legacy prefix and `?query` suffix,
+    /// then splits once on `://` to get the scheme and id. Unrecognized
+    /// schemes become `Guid::Unknown` rather than failing, since Plex adds
+    /// new agents over time and callers shouldn't need to handle an error
+    /// for every one of them.
+    pub fn parse(raw: &str) -> Guid {
+        let without_query = raw.split('?').next().unwrap_or(raw);
+        let without_agent_prefix = without_query
+            .strip_prefix(LEGACY_AGENT_PREFIX)
+            .unwrap_or(without_query);
+
+        let Some((scheme, id)) = without_agent_prefix.split_once("://") else {
+            return Guid::Unknown(raw.to_string());
+        };
+
+        match scheme {
+            "imdb" => Guid::Imdb(id.to_string()),
+            "tmdb" => Guid::Tmdb(id.to_string()),
+            "tvdb" => Guid::Tvdb(id.to_string()),
+            "mbid" => Guid::Mbid(id.to_string()),
+            "plex" => Guid::Plex(id.to_string()),
+            "local" => Guid::Local(id.to_string()),
+            "collection" => Guid::Collection(id.to_string()),
+            _ => Guid::Unknown(raw.to_string()),
+        }
+    }
+
+    /// Parse every GUID attached to a Plex metadata item.
+    ///
+    /// Older agents put a single `guid` string on the item itself; newer
+    /// agents additionally attach a `Guid` array of `{ "id": "..." }`
+    /// objects. This covers both shapes.
+    pub fn parse_all(item: &serde_json::Value) -> Vec<Guid> {
+        let mut guids = Vec::new();
+
+        if let Some(guid) = item.get("guid").and_then(serde_json::Value::as_str) {
+            guids.push(Guid::parse(guid));
+        }
+
+        if let Some(entries) = item.get("Guid").and_then(serde_json::Value::as_array) {
+            for entry in entries {
+                if let Some(id) = entry.get("id").and_then(serde_json::Value::as_str) {
+                    guids.push(Guid::parse(id));
+                }
+            }
+        }
+
+        guids
+    }
+}
+
+impl std::fmt::Display for Guid {
+    /// Human-readable `scheme: id`, e.g. `imdb: tt0111161`, for printing in
+    /// slash command output alongside the rest of this extension's
+    /// human-readable text.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (scheme, id) = match self {
+            Guid::Imdb(id) => ("imdb", id),
+            Guid::Tmdb(id) => ("tmdb", id),
+            Guid::Tvdb(id) => ("tvdb", id),
+            Guid::Mbid(id) => ("mbid", id),
+            Guid::Plex(id) => ("plex", id),
+            Guid::Local(id) => ("local", id),
+            Guid::Collection(id) => ("collection", id),
+            Guid::Unknown(raw) => ("unknown", raw),
+        };
+        write!(f, "{scheme}: {id}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_legacy_agent_uri() {
+        assert_eq!(
+            Guid::parse("com.plexapp.agents.imdb://tt0111161?lang=en"),
+            Guid::Imdb("tt0111161".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_modern_scheme_uris() {
+        assert_eq!(Guid::parse("imdb://tt0111161"), Guid::Imdb("tt0111161".to_string()));
+        assert_eq!(Guid::parse("tmdb://278"), Guid::Tmdb("278".to_string()));
+        assert_eq!(Guid::parse("tvdb://81189"), Guid::Tvdb("81189".to_string()));
+        assert_eq!(Guid::parse("mbid://abc-123"), Guid::Mbid("abc-123".to_string()));
+        assert_eq!(
+            Guid::parse("plex://movie/5d776b59"),
+            Guid::Plex("movie/5d776b59".to_string())
+        );
+    }
+
+    #[test]
+    fn falls_back_to_unknown_for_unrecognized_or_malformed_input() {
+        assert_eq!(
+            Guid::parse("anidb://12345"),
+            Guid::Unknown("anidb://12345".to_string())
+        );
+        assert_eq!(Guid::parse("not-a-guid"), Guid::Unknown("not-a-guid".to_string()));
+    }
+
+    #[test]
+    fn parse_all_reads_both_legacy_string_and_nested_array() {
+        let item = serde_json::json!({
+            "guid": "com.plexapp.agents.imdb://tt0111161",
+            "Guid": [
+                { "id": "tmdb://278" },
+                { "id": "tvdb://81189" },
+            ],
+        });
+
+        assert_eq!(
+            Guid::parse_all(&item),
+            vec![
+                Guid::Imdb("tt0111161".to_string()),
+                Guid::Tmdb("278".to_string()),
+                Guid::Tvdb("81189".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_all_returns_empty_when_item_has_no_guids() {
+        let item = serde_json::json!({ "title": "Untitled" });
+        assert!(Guid::parse_all(&item).is_empty());
+    }
+
+    #[test]
+    fn displays_as_scheme_colon_id() {
+        assert_eq!(Guid::Imdb("tt0111161".to_string()).to_string(), "imdb: tt0111161");
+        assert_eq!(Guid::Unknown("anidb://12345".to_string()).to_string(), "unknown: anidb://12345");
+    }
+}
@@ -1,22 +1,323 @@
-use zed_extension_api as zed;
+mod backend;
+mod deeplink;
+mod guid;
+mod semantic;
 
-struct PlexMediaServerExtension;
+use std::cell::RefCell;
+
+use zed::settings::ContextServerSettings;
+use zed_extension_api::{self as zed, serde_json};
+
+struct PlexMediaServerExtension {
+    // `run_slash_command`/`complete_slash_command_argument` don't receive a
+    // `Project`, so they can't call `ContextServerSettings::for_project`
+    // themselves. Stash the credentials here the one time
+    // `context_server_command` does have project access.
+    plex_url: RefCell<Option<String>>,
+    plex_token: RefCell<Option<String>>,
+}
+
+impl PlexMediaServerExtension {
+    fn client(&self) -> Result<backend::PlexClient, String> {
+        let url = self.plex_url.borrow().clone().ok_or_else(|| {
+            "Plex isn't configured yet: set PLEX_URL (and PLEX_TOKEN) on the plex-mcp context server".to_string()
+        })?;
+        let token = self.plex_token.borrow().clone().unwrap_or_default();
+        Ok(backend::PlexClient::new(url, token))
+    }
+}
+
+/// Render a list of Plex metadata items as a `SlashCommandOutput`, one
+/// section per item (title + year + summary).
+fn render_items(heading: &str, items: &[serde_json::Value]) -> zed::SlashCommandOutput {
+    if items.is_empty() {
+        let text = format!("{heading}: no results");
+        return zed::SlashCommandOutput {
+            sections: vec![zed::SlashCommandOutputSection {
+                range: (0..text.len()).into(),
+                label: heading.to_string(),
+            }],
+            text,
+        };
+    }
+
+    let mut text = String::new();
+    let mut sections = Vec::with_capacity(items.len());
+    for item in items {
+        let title = item
+            .get("title")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("Untitled");
+        let year = item.get("year").and_then(serde_json::Value::as_i64);
+        let summary = item.get("summary").and_then(serde_json::Value::as_str);
+
+        let start = text.len();
+        match year {
+            Some(year) => text.push_str(&format!("{title} ({year})\n")),
+            None => text.push_str(&format!("{title}\n")),
+        }
+        if let Some(summary) = summary.filter(|s| !s.is_empty()) {
+            text.push_str(summary);
+            text.push('\n');
+        }
+        text.push('\n');
+
+        sections.push(zed::SlashCommandOutputSection {
+            range: (start..text.len()).into(),
+            label: title.to_string(),
+        });
+    }
+
+    zed::SlashCommandOutput { text, sections }
+}
 
 impl zed::Extension for PlexMediaServerExtension {
+    fn new() -> Self {
+        Self {
+            plex_url: RefCell::new(None),
+            plex_token: RefCell::new(None),
+        }
+    }
+
     fn context_server_command(
         &mut self,
         id: &zed::ContextServerId,
-        _project: &zed::Project,
+        project: &zed::Project,
     ) -> zed::Result<zed::Command> {
         match id.0.as_str() {
-            "plex-mcp" => Ok(zed::Command {
-                command: "uv".to_string(),
-                args: vec!["run".to_string(), "plex-mcp".to_string()],
-                env: Default::default(),
-            }),
+            "plex-mcp" => {
+                let settings = ContextServerSettings::for_project("plex-mcp", project)?;
+
+                let mut env = Vec::new();
+                if let Some(url) = settings.settings.get("PLEX_URL").and_then(serde_json::Value::as_str) {
+                    *self.plex_url.borrow_mut() = Some(url.to_string());
+                    env.push(("PLEX_URL".to_string(), url.to_string()));
+                }
+                if let Some(token) = settings.settings.get("PLEX_TOKEN").and_then(serde_json::Value::as_str) {
+                    *self.plex_token.borrow_mut() = Some(token.to_string());
+                    env.push(("PLEX_TOKEN".to_string(), token.to_string()));
+                }
+                if let Some(name) = settings
+                    .settings
+                    .get("PLEX_SERVER_NAME")
+                    .and_then(serde_json::Value::as_str)
+                {
+                    env.push(("PLEX_SERVER_NAME".to_string(), name.to_string()));
+                }
+
+                // `settings.command` is Zed's own path/args/env override for
+                // people running `plex-mcp` standalone instead of via `uv`;
+                // its `args` stand entirely on their own and must not be
+                // padded with the `uv run plex-mcp` default.
+                if let Some(mut command) = settings.command {
+                    command.env.extend(env);
+                    return Ok(command);
+                }
+
+                Ok(zed::Command {
+                    command: "uv".to_string(),
+                    args: vec!["run".to_string(), "plex-mcp".to_string()],
+                    env,
+                })
+            }
             _ => Err(format!("Unknown server: {}", id.0)),
         }
     }
+
+    fn complete_slash_command_argument(
+        &self,
+        command: zed::SlashCommand,
+        args: Vec<String>,
+    ) -> zed::Result<Vec<zed::SlashCommandArgumentCompletion>, String> {
+        match command.name.as_str() {
+            "plex-recent" | "plex-onresume" | "plex-guids" | "plex-link" => Ok(Vec::new()),
+            "plex-search" | "plex-semantic" => {
+                // No Plex configured yet: there's nothing to complete against.
+                let Ok(client) = self.client() else {
+                    return Ok(Vec::new());
+                };
+                let prefix = args.join(" ");
+
+                let mut completions = Vec::new();
+
+                if let Ok(sections) = client.container_items("/library/sections", "Directory") {
+                    for section in sections {
+                        let Some(title) = section.get("title").and_then(serde_json::Value::as_str) else {
+                            continue;
+                        };
+                        if prefix.is_empty() || title.to_lowercase().starts_with(&prefix.to_lowercase()) {
+                            completions.push(zed::SlashCommandArgumentCompletion {
+                                label: format!("section: {title}"),
+                                new_text: title.to_string(),
+                                run_command: false,
+                            });
+                        }
+                    }
+                }
+
+                if !prefix.is_empty() {
+                    let path = format!("/search?query={}", backend::percent_encode(&prefix));
+                    if let Ok(items) = client.metadata_items(&path) {
+                        for item in items.iter().take(10) {
+                            if let Some(title) = item.get("title").and_then(serde_json::Value::as_str) {
+                                completions.push(zed::SlashCommandArgumentCompletion {
+                                    label: title.to_string(),
+                                    new_text: title.to_string(),
+                                    run_command: true,
+                                });
+                            }
+                        }
+                    }
+                }
+
+                Ok(completions)
+            }
+            command => Err(format!("Unknown slash command: {command}")),
+        }
+    }
+
+    fn run_slash_command(
+        &self,
+        command: zed::SlashCommand,
+        args: Vec<String>,
+        _worktree: Option<&zed::Worktree>,
+    ) -> zed::Result<zed::SlashCommandOutput, String> {
+        match command.name.as_str() {
+            "plex-search" => {
+                let query = args.join(" ");
+                if query.is_empty() {
+                    return Err("usage: /plex-search <title or keywords>".to_string());
+                }
+
+                let path = format!("/search?query={}", backend::percent_encode(&query));
+                let items = self.client()?.metadata_items(&path)?;
+                Ok(render_items(&format!("Plex search: {query}"), &items))
+            }
+            "plex-recent" => {
+                let items = self.client()?.metadata_items("/library/recentlyAdded")?;
+                Ok(render_items("Recently added", &items))
+            }
+            "plex-onresume" => {
+                let items = self.client()?.metadata_items("/library/onDeck")?;
+                Ok(render_items("On deck", &items))
+            }
+            "plex-semantic" => {
+                const USAGE: &str = "usage: /plex-semantic <description> [--section <library name>]";
+
+                let (section_filter, query_words): (Option<String>, Vec<String>) = match args.split_first() {
+                    Some((flag, rest)) if flag == "--section" => match rest.split_first() {
+                        Some((name, query_rest)) => (Some(name.clone()), query_rest.to_vec()),
+                        None => return Err(USAGE.to_string()),
+                    },
+                    _ => (None, args.clone()),
+                };
+
+                let query = query_words.join(" ");
+                if query.is_empty() {
+                    return Err(USAGE.to_string());
+                }
+
+                let client = self.client()?;
+                let sections = client.container_items("/library/sections", "Directory")?;
+
+                let mut items = Vec::new();
+                for section in sections {
+                    let Some(key) = section.get("key").and_then(serde_json::Value::as_str) else {
+                        continue;
+                    };
+                    if let Some(wanted) = &section_filter {
+                        let title = section.get("title").and_then(serde_json::Value::as_str).unwrap_or("");
+                        if !title.eq_ignore_ascii_case(wanted) {
+                            continue;
+                        }
+                    }
+                    items.extend(client.metadata_items(&format!("/library/sections/{key}/all"))?);
+                }
+
+                // This ranks by shared words (see semantic.rs), not embedding
+                // similarity, so the output is described as such rather than
+                // as "semantic" matches.
+                let matches = semantic::rank(&query, &items, 5);
+                let text = if matches.is_empty() {
+                    format!("No related items found for \"{query}\"")
+                } else {
+                    let lines: Vec<String> = matches
+                        .iter()
+                        .map(|m| format!("{} (ratingKey {}) — {:.2} — {}", m.title, m.rating_key, m.score, m.reason))
+                        .collect();
+                    format!("Related items for \"{query}\":\n{}", lines.join("\n"))
+                };
+
+                Ok(zed::SlashCommandOutput {
+                    sections: vec![zed::SlashCommandOutputSection {
+                        range: (0..text.len()).into(),
+                        label: format!("Plex related-term search: {query}"),
+                    }],
+                    text,
+                })
+            }
+            "plex-guids" => {
+                let rating_key = args.first().cloned().unwrap_or_default();
+                if rating_key.is_empty() {
+                    return Err("usage: /plex-guids <ratingKey>".to_string());
+                }
+
+                let item = self
+                    .client()?
+                    .get_json(&format!("/library/metadata/{rating_key}"))?;
+                let item = item
+                    .get("MediaContainer")
+                    .and_then(|container| container.get("Metadata"))
+                    .and_then(serde_json::Value::as_array)
+                    .and_then(|items| items.first())
+                    .ok_or_else(|| format!("no item found for ratingKey {rating_key}"))?;
+
+                let guids = guid::Guid::parse_all(item);
+                let text = if guids.is_empty() {
+                    format!("No external IDs found for ratingKey {rating_key}")
+                } else {
+                    let lines: Vec<String> = guids.iter().map(|g| g.to_string()).collect();
+                    format!("External IDs for ratingKey {rating_key}:\n{}", lines.join("\n"))
+                };
+
+                Ok(zed::SlashCommandOutput {
+                    sections: vec![zed::SlashCommandOutputSection {
+                        range: (0..text.len()).into(),
+                        label: format!("Plex external IDs: {rating_key}"),
+                    }],
+                    text,
+                })
+            }
+            "plex-link" => {
+                let raw = args.join(" ");
+                if raw.is_empty() {
+                    return Err("usage: /plex-link <plex:// URI or app.plex.tv link>".to_string());
+                }
+
+                let link = deeplink::DeepLink::parse(&raw)
+                    .ok_or_else(|| format!("not a recognized Plex deep link: {raw}"))?;
+
+                let client = self.client()?;
+
+                // The link may name a different server than the one
+                // configured here; resolving against the wrong server could
+                // silently return an unrelated item if ratingKeys collide,
+                // so refuse rather than guessing.
+                if let Some(wanted) = &link.machine_identifier {
+                    let configured = client.machine_identifier()?;
+                    if *wanted != configured {
+                        return Err(format!(
+                            "this link points at Plex server {wanted}, but the configured server is {configured}"
+                        ));
+                    }
+                }
+
+                let items = client.metadata_items(&link.metadata_key)?;
+                Ok(render_items(&format!("Plex link: {}", link.metadata_key), &items))
+            }
+            command => Err(format!("Unknown slash command: {command}")),
+        }
+    }
 }
 
 zed::register_extension!(PlexMediaServerExtension);
@@ -0,0 +1,148 @@
+//! A lexical stand-in for semantic library search.
+//!
+//! True semantic search needs an embedding model and a vector index, and
+//! neither is something a Zed wasm extension can vendor on its own — that
+//! belongs in the plex-mcp backend. Until it lands there, `/plex-semantic`
+//! ranks items by token overlap between the query and each item's title,
+//! summary, tagline, and genres. It's a much cruder signal than an
+//! embedding, but it's a real ranking over real library data rather than a
+//! fixed response string, and the scoring can be swapped for an embedding
+//! call later without changing how callers use `rank`.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use zed_extension_api::serde_json;
+
+/// One ranked library item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub rating_key: String,
+    pub title: String,
+    pub score: f64,
+    pub reason: String,
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn item_text(item: &serde_json::Value) -> String {
+    let mut parts = Vec::new();
+    for field in ["title", "summary", "tagline"] {
+        if let Some(value) = item.get(field).and_then(serde_json::Value::as_str) {
+            parts.push(value.to_string());
+        }
+    }
+    if let Some(genres) = item.get("Genre").and_then(serde_json::Value::as_array) {
+        for genre in genres {
+            if let Some(tag) = genre.get("tag").and_then(serde_json::Value::as_str) {
+                parts.push(tag.to_string());
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+/// Rank `items` against `query` by Jaccard token overlap and return the top
+/// `limit` matches, each with a `[0, 1]` score and the words that matched.
+/// Items with no overlap at all are dropped rather than ranked last.
+pub fn rank(query: &str, items: &[serde_json::Value], limit: usize) -> Vec<Match> {
+    let query_tokens = tokens(query);
+    if query_tokens.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<Match> = items
+        .iter()
+        .filter_map(|item| {
+            let rating_key = item
+                .get("ratingKey")
+                .and_then(serde_json::Value::as_str)?
+                .to_string();
+            let title = item
+                .get("title")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("Untitled")
+                .to_string();
+
+            let item_tokens = tokens(&item_text(item));
+            let overlap: HashSet<&String> = query_tokens.intersection(&item_tokens).collect();
+            if overlap.is_empty() {
+                return None;
+            }
+
+            let union_len = query_tokens.union(&item_tokens).count();
+            let score = overlap.len() as f64 / union_len as f64;
+
+            let mut matched_words: Vec<&str> = overlap.into_iter().map(String::as_str).collect();
+            matched_words.sort_unstable();
+            let reason = format!("matched: {}", matched_words.join(", "));
+
+            Some(Match {
+                rating_key,
+                title,
+                score,
+                reason,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_higher_overlap_first() {
+        let items = serde_json::json!([
+            { "ratingKey": "1", "title": "Arrival", "summary": "A linguist deciphers the language of aliens." },
+            { "ratingKey": "2", "title": "Contact", "summary": "A scientist makes first contact with aliens." },
+        ]);
+        let items = items.as_array().unwrap();
+
+        let matches = rank("first contact with aliens", items, 5);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].rating_key, "2");
+        assert!(matches[0].score > matches[1].score);
+    }
+
+    #[test]
+    fn drops_items_with_no_overlap() {
+        let items = serde_json::json!([
+            { "ratingKey": "1", "title": "Arrival", "summary": "A linguist deciphers an alien language." },
+        ]);
+        let items = items.as_array().unwrap();
+
+        assert!(rank("romantic comedy wedding", items, 5).is_empty());
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        let items = serde_json::json!([{ "ratingKey": "1", "title": "Arrival" }]);
+        let items = items.as_array().unwrap();
+
+        assert!(rank("", items, 5).is_empty());
+    }
+
+    #[test]
+    fn respects_limit() {
+        let items = serde_json::json!([
+            { "ratingKey": "1", "title": "Sci-Fi A", "summary": "space aliens" },
+            { "ratingKey": "2", "title": "Sci-Fi B", "summary": "space aliens" },
+            { "ratingKey": "3", "title": "Sci-Fi C", "summary": "space aliens" },
+        ]);
+        let items = items.as_array().unwrap();
+
+        assert_eq!(rank("space aliens", items, 2).len(), 2);
+    }
+}